@@ -1,18 +1,15 @@
+mod logging;
+mod pocketbase;
+
 use tauri::Manager;
-use tauri_plugin_shell::ShellExt;
 use std::sync::Mutex;
-use std::net::TcpListener;
-use log::{info, warn, error, debug};
-
-struct PocketBaseState {
-    child: Option<tauri_plugin_shell::process::CommandChild>,
-}
+use log::{info, error, debug};
 
-fn is_port_available(port: u16) -> bool {
-    let available = TcpListener::bind(("127.0.0.1", port)).is_ok();
-    debug!("Port {} availability check: {}", port, available);
-    available
-}
+use pocketbase::commands::{pocketbase_base_url, pocketbase_restart, pocketbase_status};
+use pocketbase::lock;
+use pocketbase::shutdown;
+use pocketbase::spawn::spawn_sidecar;
+use pocketbase::PocketBaseState;
 
 #[tauri::command]
 fn greet(name: String) -> String {
@@ -20,19 +17,40 @@ fn greet(name: String) -> String {
     format!("Hello, {}! Welcome to Moonsway.", name)
 }
 
+/// Returns the path to Moonsway's active log file, so the UI can offer an
+/// "open logs" action.
+#[tauri::command]
+fn log_file_path(app: tauri::AppHandle) -> Result<String, String> {
+    let log_dir = app.path().app_log_dir().map_err(|e| e.to_string())?;
+    Ok(logging::log_file_path(&log_dir).to_string_lossy().to_string())
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    // Initialize logger (only in debug builds)
-    #[cfg(debug_assertions)]
-    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
-    
     info!("Moonsway starting up...");
-    
+
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
-        .manage(Mutex::new(PocketBaseState { child: None }))
+        .manage(Mutex::new(PocketBaseState::new()))
         .setup(|app| {
+            let log_dir = app
+                .path()
+                .app_log_dir()
+                .expect("failed to resolve app log dir");
+            let cache_dir = app
+                .path()
+                .app_cache_dir()
+                .expect("failed to resolve app cache dir");
+
+            if let Err(e) = logging::init(&log_dir) {
+                // Logging isn't up yet, so this has to go to stderr directly.
+                eprintln!("Failed to initialize file logger: {}", e);
+            }
+
             info!("Running setup hook...");
+            info!("Log directory: {:?}", log_dir);
+            info!("Cache directory: {:?}", cache_dir);
+
             let app_data_dir = app
                 .path()
                 .app_data_dir()
@@ -48,72 +66,30 @@ pub fn run() {
             let data_dir = app_data_dir.to_string_lossy().to_string();
             info!("PocketBase data directory: {}", data_dir);
 
-            if !is_port_available(8090) {
-                warn!("Port 8090 already in use - PocketBase may already be running");
-                info!("Skipping PocketBase sidecar spawn");
-            } else {
-                info!("Starting PocketBase sidecar on 127.0.0.1:8090...");
-                let sidecar_command = app
-                    .shell()
-                    .sidecar("pocketbase")
-                    .expect("failed to create sidecar command")
-                    .args(["serve", "--http", "127.0.0.1:8090", "--dir", &data_dir]);
-
-                let (mut rx, child) = match sidecar_command.spawn() {
-                    Ok(result) => result,
-                    Err(e) => {
+            match lock::negotiate(&app_data_dir) {
+                lock::Negotiation::Reuse { pid, port } => {
+                    info!(
+                        "Found a live PocketBase instance on port {} - reusing it",
+                        port
+                    );
+                    {
+                        let state = app.state::<Mutex<PocketBaseState>>();
+                        let mut state = state.lock().unwrap();
+                        state.port = Some(port);
+                        state.external_pid = Some(pid);
+                    }
+                    pocketbase::commands::emit_status(app.handle());
+                }
+                lock::Negotiation::Spawn { port } => {
+                    if let Err(e) = spawn_sidecar(app.handle(), &data_dir, port) {
                         error!("Failed to spawn PocketBase sidecar: {}", e);
                         return Err(e.into());
                     }
-                };
-
-                info!("PocketBase sidecar spawned successfully");
+                }
+            }
 
-                // Store child process so we can kill it on exit
-                let state = app.state::<Mutex<PocketBaseState>>();
-                state.lock().unwrap().child = Some(child);
-                debug!("PocketBase child process stored in state");
+            shutdown::register_signal_handlers(app.handle().clone());
 
-                // Log PocketBase output
-                tauri::async_runtime::spawn(async move {
-                    use tauri_plugin_shell::process::CommandEvent;
-                    while let Some(event) = rx.recv().await {
-                        match event {
-                            CommandEvent::Stdout(line) => {
-                                let output = String::from_utf8_lossy(&line);
-                                info!("[PocketBase] {}", output.trim());
-                            }
-                            CommandEvent::Stderr(line) => {
-                                let output = String::from_utf8_lossy(&line);
-                                if output.contains("Error") || output.contains("error") {
-                                    error!("[PocketBase] {}", output.trim());
-                                } else {
-                                    warn!("[PocketBase] {}", output.trim());
-                                }
-                            }
-                            CommandEvent::Terminated(status) => {
-                                if let Some(code) = status.code {
-                                    if code == 0 {
-                                        info!("[PocketBase] Process terminated cleanly (exit code: 0)");
-                                    } else {
-                                        error!("[PocketBase] Process terminated with error (exit code: {})", code);
-                                    }
-                                } else {
-                                    warn!("[PocketBase] Process terminated (no exit code)");
-                                }
-                                break;
-                            }
-                            CommandEvent::Error(err) => {
-                                error!("[PocketBase] Process error: {}", err);
-                            }
-                            _ => {}
-                        }
-                    }
-                });
-
-                info!("PocketBase sidecar ready on http://127.0.0.1:8090");
-            }
-            
             info!("Setup completed successfully");
 
             Ok(())
@@ -121,18 +97,19 @@ pub fn run() {
         .on_window_event(|window, event| {
             if let tauri::WindowEvent::Destroyed = event {
                 info!("Window destroyed - cleaning up PocketBase sidecar...");
-                let state = window.state::<Mutex<PocketBaseState>>();
-                if let Some(child) = state.lock().unwrap().child.take() {
-                    match child.kill() {
-                        Ok(_) => info!("PocketBase sidecar stopped successfully"),
-                        Err(e) => error!("Failed to stop PocketBase sidecar: {}", e),
-                    }
-                } else {
-                    debug!("No PocketBase child process to clean up");
-                }
+                let app_handle = window.app_handle().clone();
+                tauri::async_runtime::spawn(async move {
+                    shutdown::graceful_shutdown(&app_handle).await;
+                });
             }
         })
-        .invoke_handler(tauri::generate_handler![greet])
+        .invoke_handler(tauri::generate_handler![
+            greet,
+            log_file_path,
+            pocketbase_base_url,
+            pocketbase_status,
+            pocketbase_restart,
+        ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
     
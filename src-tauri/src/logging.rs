@@ -0,0 +1,184 @@
+//! Persistent, rotating log files in the platform log directory.
+//!
+//! Previously logging only worked under `#[cfg(debug_assertions)]` via
+//! `env_logger` writing to stderr, so release builds produced no
+//! diagnostics at all - and the `[PocketBase]` lines forwarded from the
+//! sidecar's stdout/stderr vanished right along with everything else once
+//! there was no console to see them on. This installs a single global
+//! logger that always writes to a size-rotated file, additionally echoing
+//! to stderr in debug builds.
+
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use log::{LevelFilter, Metadata, Record};
+
+const LOG_FILE_NAME: &str = "moonsway.log";
+const MAX_FILE_BYTES: u64 = 5 * 1024 * 1024;
+const MAX_ROTATED_FILES: u32 = 5;
+
+/// Path to the active (not-yet-rotated) log file inside `log_dir`.
+pub fn log_file_path(log_dir: &Path) -> PathBuf {
+    log_dir.join(LOG_FILE_NAME)
+}
+
+struct RotatingFileLogger {
+    dir: PathBuf,
+    file: Mutex<File>,
+    level: LevelFilter,
+}
+
+impl RotatingFileLogger {
+    fn open(dir: &Path) -> std::io::Result<File> {
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(log_file_path(dir))
+    }
+
+    /// Renames `moonsway.log.{i}` to `moonsway.log.{i+1}` for each rotated
+    /// file (dropping anything past `MAX_ROTATED_FILES`), then moves the
+    /// current log to `moonsway.log.1` and opens a fresh one.
+    fn rotate(&self) {
+        let current = log_file_path(&self.dir);
+
+        for i in (1..MAX_ROTATED_FILES).rev() {
+            let from = self.dir.join(format!("{}.{}", LOG_FILE_NAME, i));
+            let to = self.dir.join(format!("{}.{}", LOG_FILE_NAME, i + 1));
+            let _ = fs::rename(&from, &to);
+        }
+        let _ = fs::rename(&current, self.dir.join(format!("{}.1", LOG_FILE_NAME)));
+
+        if let Ok(file) = Self::open(&self.dir) {
+            *self.file.lock().unwrap() = file;
+        }
+    }
+}
+
+impl log::Log for RotatingFileLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let line = format!(
+            "{} {:<5} [{}] {}\n",
+            format_timestamp(),
+            record.level(),
+            record.target(),
+            record.args()
+        );
+
+        #[cfg(debug_assertions)]
+        eprint!("{}", line);
+
+        let mut file = self.file.lock().unwrap();
+        let _ = file.write_all(line.as_bytes());
+
+        let size = fs::metadata(log_file_path(&self.dir))
+            .map(|m| m.len())
+            .unwrap_or(0);
+        if size >= MAX_FILE_BYTES {
+            drop(file);
+            self.rotate();
+        }
+    }
+
+    fn flush(&self) {
+        let _ = self.file.lock().unwrap().flush();
+    }
+}
+
+/// Formats the current time as `YYYY-MM-DD HH:MM:SS` UTC without pulling in
+/// a datetime crate, using the standard days-since-epoch civil calendar
+/// conversion.
+fn format_timestamp() -> String {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    format_timestamp_at(now.as_secs())
+}
+
+/// Does the actual civil-calendar conversion for `format_timestamp`, taking
+/// seconds-since-epoch directly so the math can be unit tested against known
+/// dates without depending on the clock.
+fn format_timestamp_at(secs: u64) -> String {
+    let (days, time_of_day) = (secs / 86_400, secs % 86_400);
+    let (hour, min, sec) = (time_of_day / 3600, (time_of_day / 60) % 60, time_of_day % 60);
+
+    // Howard Hinnant's civil_from_days algorithm.
+    let z = days as i64 + 719_468;
+    let era = if z >= 0 { z } else { z - 146_096 } / 146_097;
+    let doe = (z - era * 146_097) as u64;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let y = yoe as i64 + era * 400;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = if month <= 2 { y + 1 } else { y };
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year, month, day, hour, min, sec
+    )
+}
+
+/// Installs the rotating file logger as the global `log` sink. Must be
+/// called exactly once, before any logging macros are expected to take
+/// effect, and only after `log_dir` can be resolved (i.e. from inside the
+/// Tauri `setup` hook).
+pub fn init(log_dir: &Path) -> std::io::Result<PathBuf> {
+    fs::create_dir_all(log_dir)?;
+
+    let level = std::env::var("RUST_LOG")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(LevelFilter::Info);
+
+    let logger = RotatingFileLogger {
+        dir: log_dir.to_path_buf(),
+        file: Mutex::new(RotatingFileLogger::open(log_dir)?),
+        level,
+    };
+
+    log::set_max_level(level);
+    let _ = log::set_boxed_logger(Box::new(logger));
+
+    Ok(log_file_path(log_dir))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_timestamp_at_epoch() {
+        assert_eq!(format_timestamp_at(0), "1970-01-01 00:00:00");
+    }
+
+    #[test]
+    fn format_timestamp_at_known_date() {
+        // 2024-03-05 12:34:56 UTC
+        assert_eq!(format_timestamp_at(1_709_642_096), "2024-03-05 12:34:56");
+    }
+
+    #[test]
+    fn format_timestamp_at_leap_day() {
+        // 2024-02-29 00:00:00 UTC
+        assert_eq!(format_timestamp_at(1_709_164_800), "2024-02-29 00:00:00");
+    }
+
+    #[test]
+    fn format_timestamp_at_year_end_rollover() {
+        // 2023-12-31 23:59:59 UTC
+        assert_eq!(format_timestamp_at(1_704_067_199), "2023-12-31 23:59:59");
+    }
+}
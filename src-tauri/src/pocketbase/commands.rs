@@ -0,0 +1,186 @@
+//! Frontend-facing lifecycle API for the PocketBase sidecar.
+
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+
+use log::info;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::shutdown;
+use super::spawn::spawn_sidecar;
+use super::state::PocketBaseState;
+
+/// Emitted whenever the sidecar's status changes, so the UI can reactively
+/// reflect backend health instead of polling `pocketbase_status`.
+pub const STATUS_EVENT: &str = "pocketbase://status";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "state", rename_all = "camelCase")]
+pub enum PocketBaseStatus {
+    NotStarted,
+    Starting,
+    Running { pid: u32, port: u16, uptime_secs: u64 },
+    Crashed { code: i32 },
+    Stopped,
+}
+
+fn compute_status(state: &PocketBaseState) -> PocketBaseStatus {
+    if let Some(child) = &state.child {
+        let port = state.port.expect("port is set whenever child is");
+        if state.ready.load(Ordering::SeqCst) {
+            let uptime_secs = state
+                .started_at
+                .map(|t| t.elapsed().as_secs())
+                .unwrap_or(0);
+            PocketBaseStatus::Running {
+                pid: child.pid(),
+                port,
+                uptime_secs,
+            }
+        } else {
+            PocketBaseStatus::Starting
+        }
+    } else if let (Some(pid), Some(port)) = (state.external_pid, state.port) {
+        // A foreign instance we're reusing via the lock file - we never
+        // held a `CommandChild` for it, so we can't report its uptime.
+        PocketBaseStatus::Running {
+            pid,
+            port,
+            uptime_secs: 0,
+        }
+    } else if let Some(code) = state.last_exit_code {
+        PocketBaseStatus::Crashed { code }
+    } else if state.port.is_some() {
+        PocketBaseStatus::Stopped
+    } else {
+        PocketBaseStatus::NotStarted
+    }
+}
+
+/// Recomputes the current status and emits it as [`STATUS_EVENT`].
+pub fn emit_status(app: &AppHandle) {
+    let state = app.state::<Mutex<PocketBaseState>>();
+    let status = compute_status(&state.lock().unwrap());
+    let _ = app.emit(STATUS_EVENT, status);
+}
+
+/// Base URL of the negotiated PocketBase instance, e.g. `http://127.0.0.1:8090`.
+#[tauri::command]
+pub fn pocketbase_base_url(app: AppHandle) -> Result<String, String> {
+    let state = app.state::<Mutex<PocketBaseState>>();
+    let port = state
+        .lock()
+        .unwrap()
+        .port
+        .ok_or_else(|| "PocketBase has not been started yet".to_string())?;
+    Ok(format!("http://127.0.0.1:{}", port))
+}
+
+/// Current lifecycle status of the PocketBase sidecar.
+#[tauri::command]
+pub fn pocketbase_status(app: AppHandle) -> PocketBaseStatus {
+    let state = app.state::<Mutex<PocketBaseState>>();
+    compute_status(&state.lock().unwrap())
+}
+
+/// Stops the current PocketBase sidecar and respawns it on the same port.
+///
+/// Only applies to a sidecar we actually own a `CommandChild` for. If we're
+/// reusing a foreign instance via the lock file (`external_pid`), we have no
+/// way to stop it - spawning a new one on the same port would just race it
+/// for the listening socket and crash-loop, so this is rejected up front.
+#[tauri::command]
+pub async fn pocketbase_restart(app: AppHandle) -> Result<(), String> {
+    let (data_dir, port) = {
+        let state = app.state::<Mutex<PocketBaseState>>();
+        let state = state.lock().unwrap();
+        if state.external_pid.is_some() {
+            return Err(
+                "PocketBase is running as a reused instance from another Moonsway process - \
+                 stop that process directly instead of restarting from here"
+                    .to_string(),
+            );
+        }
+        let port = state
+            .port
+            .ok_or_else(|| "PocketBase has not been started yet".to_string())?;
+        drop(state);
+        let data_dir = app
+            .path()
+            .app_data_dir()
+            .map_err(|e| e.to_string())?
+            .to_string_lossy()
+            .to_string();
+        (data_dir, port)
+    };
+
+    info!("Restart requested - stopping current PocketBase sidecar");
+    {
+        let state = app.state::<Mutex<PocketBaseState>>();
+        // Suppress the supervisor so it doesn't race our own respawn below.
+        state.lock().unwrap().should_supervise.store(false, Ordering::SeqCst);
+    }
+    shutdown::stop_child(&app).await;
+    emit_status(&app);
+
+    spawn_sidecar(&app, &data_dir, port).map_err(|e| e.to_string())?;
+
+    let state = app.state::<Mutex<PocketBaseState>>();
+    let mut state = state.lock().unwrap();
+    state.should_supervise.store(true, Ordering::SeqCst);
+    state.restart_attempts = 0;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // `PocketBaseState::child` is a real `CommandChild` from a spawned
+    // sidecar, which can't be constructed without actually spawning a
+    // process - so the branches below exercise every path through
+    // `compute_status` that doesn't require one. The `child.is_some()`
+    // branches (`Running`/`Starting` for a sidecar we own) are covered by
+    // running the app.
+
+    #[test]
+    fn not_started_by_default() {
+        let state = PocketBaseState::new();
+        assert!(matches!(compute_status(&state), PocketBaseStatus::NotStarted));
+    }
+
+    #[test]
+    fn stopped_once_a_port_was_negotiated_but_nothing_is_running() {
+        let mut state = PocketBaseState::new();
+        state.port = Some(8090);
+        assert!(matches!(compute_status(&state), PocketBaseStatus::Stopped));
+    }
+
+    #[test]
+    fn crashed_reports_the_last_exit_code() {
+        let mut state = PocketBaseState::new();
+        state.port = Some(8090);
+        state.last_exit_code = Some(1);
+        match compute_status(&state) {
+            PocketBaseStatus::Crashed { code } => assert_eq!(code, 1),
+            other => panic!("expected Crashed, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn running_for_a_reused_external_instance() {
+        let mut state = PocketBaseState::new();
+        state.port = Some(8090);
+        state.external_pid = Some(4242);
+        match compute_status(&state) {
+            PocketBaseStatus::Running { pid, port, uptime_secs } => {
+                assert_eq!(pid, 4242);
+                assert_eq!(port, 8090);
+                assert_eq!(uptime_secs, 0);
+            }
+            other => panic!("expected Running, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,82 @@
+//! Readiness probing for the PocketBase sidecar's HTTP endpoint.
+//!
+//! `spawn()` returning only means the process has started, not that it has
+//! bound its HTTP listener yet - a command fired right after startup would
+//! race it. We poll `/api/health` until it responds before telling the
+//! webview the backend is usable.
+
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use log::{error, info};
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::commands;
+use super::lock;
+use super::shutdown;
+use super::state::PocketBaseState;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+const READY_DEADLINE: Duration = Duration::from_secs(15);
+const PROBE_TIMEOUT: Duration = Duration::from_millis(200);
+
+/// Emitted once `/api/health` responds with 200.
+pub const READY_EVENT: &str = "pocketbase://ready";
+/// Emitted if the deadline passes without a healthy response.
+pub const ERROR_EVENT: &str = "pocketbase://error";
+
+/// Polls `/api/health` on `port` until it responds with 200, emitting
+/// [`READY_EVENT`] to the webview once it does. If [`READY_DEADLINE`]
+/// passes without success, emits [`ERROR_EVENT`] and triggers shutdown.
+///
+/// `generation` is the generation this process was spawned under (see
+/// `PocketBaseState::generation`). If a newer generation has already
+/// replaced it - e.g. the supervisor already respawned, or the sidecar was
+/// deliberately stopped - by the time either branch below is reached, this
+/// stale probe bails out instead of touching state or shutting things down.
+pub async fn wait_until_ready(app: &AppHandle, port: u16, generation: u64) {
+    let deadline = Instant::now() + READY_DEADLINE;
+
+    while Instant::now() < deadline {
+        // `check_health` does blocking socket I/O (up to `PROBE_TIMEOUT`
+        // per call) - run it on a blocking-pool thread so it can't stall a
+        // tokio worker that other tasks (signal handlers, command
+        // invocations) are relying on.
+        let healthy = tokio::task::spawn_blocking(move || lock::check_health(port, PROBE_TIMEOUT))
+            .await
+            .unwrap_or(false);
+
+        if healthy {
+            let state = app.state::<Mutex<PocketBaseState>>();
+            if state.lock().unwrap().generation != generation {
+                // Superseded mid-probe by a respawn or a deliberate stop -
+                // not our place to declare readiness or touch state anymore.
+                return;
+            }
+            info!("PocketBase sidecar ready on http://127.0.0.1:{}", port);
+            state.lock().unwrap().ready.store(true, Ordering::SeqCst);
+            let _ = app.emit(READY_EVENT, port);
+            commands::emit_status(app);
+            return;
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+
+    {
+        let state = app.state::<Mutex<PocketBaseState>>();
+        if state.lock().unwrap().generation != generation {
+            // A newer respawn (or a deliberate stop) has already
+            // superseded this generation - our deadline firing now is
+            // stale and must not trigger a global shutdown.
+            return;
+        }
+    }
+
+    error!(
+        "PocketBase did not become ready on port {} within {:?}",
+        port, READY_DEADLINE
+    );
+    let _ = app.emit(ERROR_EVENT, "PocketBase failed to become ready in time");
+    shutdown::graceful_shutdown(app).await;
+}
@@ -0,0 +1,216 @@
+//! Single-instance coordination via a lock file in the app data dir.
+//!
+//! The lock file stores `{pid}\n{port}` for the PocketBase sidecar currently
+//! (or most recently) owned by this app. On startup we use it to detect a
+//! still-running instance from another window (or a stale one left behind by
+//! a crash) instead of blindly probing a hardcoded port.
+
+use std::io::Write;
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::Duration;
+
+use log::{debug, warn};
+
+const LOCK_FILE_NAME: &str = "pocketbase.lock";
+const PORT_SCAN_START: u16 = 8090;
+const PORT_SCAN_END: u16 = 8190;
+
+pub fn lock_file_path(app_data_dir: &Path) -> PathBuf {
+    app_data_dir.join(LOCK_FILE_NAME)
+}
+
+/// A lock file's parsed contents.
+pub struct LockInfo {
+    pub pid: u32,
+    pub port: u16,
+}
+
+/// Reads and parses the lock file, if it exists and is well-formed.
+pub fn read_lock(path: &Path) -> Option<LockInfo> {
+    let contents = std::fs::read_to_string(path).ok()?;
+    let mut lines = contents.lines();
+    let pid: u32 = lines.next()?.trim().parse().ok()?;
+    let port: u16 = lines.next()?.trim().parse().ok()?;
+    Some(LockInfo { pid, port })
+}
+
+/// Atomically writes `{pid}\n{port}` to the lock file (write to a temp file,
+/// then rename over the target so readers never observe a partial write).
+pub fn write_lock(path: &Path, pid: u32, port: u16) -> std::io::Result<()> {
+    let tmp_path = path.with_extension("lock.tmp");
+    {
+        let mut tmp = std::fs::File::create(&tmp_path)?;
+        write!(tmp, "{}\n{}", pid, port)?;
+        tmp.sync_all()?;
+    }
+    std::fs::rename(&tmp_path, path)
+}
+
+pub fn remove_lock(path: &Path) {
+    if let Err(e) = std::fs::remove_file(path) {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            warn!("Failed to remove PocketBase lock file: {}", e);
+        }
+    }
+}
+
+/// Checks whether `pid` refers to a currently-running process.
+pub fn is_process_alive(pid: u32) -> bool {
+    #[cfg(unix)]
+    {
+        Command::new("kill")
+            .args(["-0", &pid.to_string()])
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+    }
+    #[cfg(windows)]
+    {
+        Command::new("tasklist")
+            .args(["/FI", &format!("PID eq {}", pid), "/NH"])
+            .output()
+            .map(|o| {
+                String::from_utf8_lossy(&o.stdout).contains(&pid.to_string())
+            })
+            .unwrap_or(false)
+    }
+}
+
+/// Performs a blocking GET against `/api/health` on `port`, returning true on
+/// a response that starts with `HTTP/1.1 200` (or `HTTP/1.0 200`).
+pub fn check_health(port: u16, timeout: Duration) -> bool {
+    use std::io::Read;
+    use std::net::{SocketAddr, TcpStream};
+
+    let addr: SocketAddr = match format!("127.0.0.1:{}", port).parse() {
+        Ok(a) => a,
+        Err(_) => return false,
+    };
+    let mut stream = match TcpStream::connect_timeout(&addr, timeout) {
+        Ok(s) => s,
+        Err(_) => return false,
+    };
+    if stream.set_read_timeout(Some(timeout)).is_err() {
+        return false;
+    }
+    let request = format!(
+        "GET /api/health HTTP/1.1\r\nHost: 127.0.0.1:{}\r\nConnection: close\r\n\r\n",
+        port
+    );
+    if stream.write_all(request.as_bytes()).is_err() {
+        return false;
+    }
+    let mut buf = [0u8; 32];
+    let n = match stream.read(&mut buf) {
+        Ok(n) => n,
+        Err(_) => return false,
+    };
+    let response = String::from_utf8_lossy(&buf[..n]);
+    response.starts_with("HTTP/1.1 200") || response.starts_with("HTTP/1.0 200")
+}
+
+/// Picks a free port by letting the OS assign one, falling back to scanning
+/// a fixed range starting at 8090 if that fails for some reason.
+pub fn pick_free_port() -> Option<u16> {
+    if let Ok(listener) = TcpListener::bind(("127.0.0.1", 0)) {
+        if let Ok(addr) = listener.local_addr() {
+            debug!("OS-assigned free port: {}", addr.port());
+            return Some(addr.port());
+        }
+    }
+
+    for port in PORT_SCAN_START..=PORT_SCAN_END {
+        if TcpListener::bind(("127.0.0.1", port)).is_ok() {
+            debug!("Found free port via scan: {}", port);
+            return Some(port);
+        }
+    }
+
+    None
+}
+
+/// Negotiation outcome: either an existing, healthy instance to reuse, or a
+/// freshly-picked port to spawn a new one on.
+pub enum Negotiation {
+    Reuse { pid: u32, port: u16 },
+    Spawn { port: u16 },
+}
+
+/// Reads the lock file (if any) and decides whether to reuse a live instance
+/// or negotiate a fresh port for a new one. Stale locks (dead PID, or a PID
+/// whose health endpoint doesn't respond) are treated as leftovers and
+/// overwritten by the caller once it spawns.
+pub fn negotiate(app_data_dir: &Path) -> Negotiation {
+    let path = lock_file_path(app_data_dir);
+
+    if let Some(info) = read_lock(&path) {
+        if is_process_alive(info.pid) && check_health(info.port, Duration::from_millis(300)) {
+            debug!(
+                "Reusing existing PocketBase instance from lock file (pid {}, port {})",
+                info.pid, info.port
+            );
+            return Negotiation::Reuse {
+                pid: info.pid,
+                port: info.port,
+            };
+        }
+        warn!(
+            "Stale PocketBase lock file found (pid {}, port {}) - will overwrite",
+            info.pid, info.port
+        );
+    }
+
+    let port = pick_free_port().unwrap_or(PORT_SCAN_START);
+    Negotiation::Spawn { port }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_then_read_round_trip() {
+        let dir = std::env::temp_dir().join(format!("moonsway-lock-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = lock_file_path(&dir);
+
+        write_lock(&path, 1234, 8091).unwrap();
+        let info = read_lock(&path).expect("lock file should parse");
+        assert_eq!(info.pid, 1234);
+        assert_eq!(info.port, 8091);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn write_lock_leaves_no_temp_file_behind() {
+        let dir = std::env::temp_dir().join(format!("moonsway-lock-test-tmp-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = lock_file_path(&dir);
+
+        write_lock(&path, 1, 8090).unwrap();
+        assert!(!path.with_extension("lock.tmp").exists());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn read_lock_missing_file_is_none() {
+        let dir = std::env::temp_dir().join(format!("moonsway-lock-test-missing-{}", std::process::id()));
+        assert!(read_lock(&lock_file_path(&dir)).is_none());
+    }
+
+    #[test]
+    fn read_lock_malformed_contents_is_none() {
+        let dir = std::env::temp_dir().join(format!("moonsway-lock-test-bad-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = lock_file_path(&dir);
+        std::fs::write(&path, "not a pid\nnot a port").unwrap();
+
+        assert!(read_lock(&path).is_none());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
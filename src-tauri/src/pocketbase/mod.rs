@@ -0,0 +1,11 @@
+//! PocketBase sidecar management: port negotiation, lifecycle and state.
+
+pub mod commands;
+pub mod health;
+pub mod lock;
+pub mod shutdown;
+pub mod spawn;
+pub mod state;
+pub mod supervisor;
+
+pub use state::PocketBaseState;
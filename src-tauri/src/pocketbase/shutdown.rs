@@ -0,0 +1,155 @@
+//! Graceful, multi-phase shutdown of the PocketBase sidecar.
+//!
+//! Modeled as a small toplevel/subsystem handle: the toplevel (this module)
+//! owns the single shutdown path, and subsystems (the OS signal listeners,
+//! the window-destroyed event) are just different triggers that all funnel
+//! into the same `graceful_shutdown` routine, which is guaranteed to run
+//! exactly once no matter which trigger fires first.
+
+use std::process::Command;
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use log::{error, info, warn};
+use tauri::{AppHandle, Manager};
+
+use super::lock;
+use super::state::PocketBaseState;
+
+/// How long to wait for PocketBase to exit on its own after a graceful
+/// termination request before we fall back to a hard kill.
+const GRACEFUL_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Sends a termination request to `pid` (SIGTERM on unix) rather than
+/// killing it outright, giving PocketBase a chance to flush its SQLite WAL.
+///
+/// Spawns and waits on the `kill` subprocess, which is blocking I/O - run on
+/// a blocking-pool thread so it can't stall a tokio worker that other tasks
+/// (signal handlers, command invocations) are relying on.
+async fn request_termination(pid: u32) {
+    #[cfg(unix)]
+    {
+        let result = tokio::task::spawn_blocking(move || {
+            Command::new("kill").args(["-TERM", &pid.to_string()]).output()
+        })
+        .await;
+        if let Ok(Err(e)) = result {
+            warn!("Failed to send SIGTERM to PocketBase (pid {}): {}", pid, e);
+        }
+    }
+    #[cfg(windows)]
+    {
+        // Windows has no SIGTERM equivalent for arbitrary processes; the
+        // caller falls back to a hard kill after the grace period elapses.
+        let _ = pid;
+    }
+}
+
+/// Requests termination of the currently-running child (if any) and waits
+/// for it to exit, polling the same notifier the stdout logging task uses,
+/// force-killing it if it hasn't exited within [`GRACEFUL_TIMEOUT`].
+///
+/// Does not touch `should_supervise` or the lock file - callers that want
+/// this to look like a deliberate stop (rather than a crash the supervisor
+/// should react to) must disable supervision first.
+pub(super) async fn stop_child(app: &AppHandle) {
+    let state = app.state::<Mutex<PocketBaseState>>();
+
+    let (child, process_exited) = {
+        let mut state = state.lock().unwrap();
+        (state.child.take(), state.process_exited.clone())
+    };
+
+    let Some(mut child) = child else {
+        info!("No PocketBase child process to stop");
+        return;
+    };
+
+    // Register as a waiter before sending the termination request, so an
+    // exit that is observed while the request is still in flight is not missed.
+    let exited = process_exited.notified();
+    tokio::pin!(exited);
+    exited.as_mut().enable();
+
+    request_termination(child.pid()).await;
+
+    let exited_gracefully = tokio::time::timeout(GRACEFUL_TIMEOUT, exited)
+        .await
+        .is_ok();
+
+    if exited_gracefully {
+        info!("PocketBase sidecar exited gracefully");
+    } else {
+        warn!(
+            "PocketBase sidecar did not exit within {:?} - forcing kill",
+            GRACEFUL_TIMEOUT
+        );
+        if let Err(e) = child.kill() {
+            error!("Failed to force-kill PocketBase sidecar: {}", e);
+        }
+    }
+}
+
+/// Runs the graceful shutdown sequence exactly once: disables crash
+/// supervision, stops the child (see [`stop_child`]), and removes the lock
+/// file once the sidecar is gone.
+///
+/// Safe to call from multiple triggers (signal handlers, window-destroyed
+/// event) concurrently - only the first caller does any work.
+pub async fn graceful_shutdown(app: &AppHandle) {
+    let state = app.state::<Mutex<PocketBaseState>>();
+
+    let already_shutting_down = {
+        let mut state = state.lock().unwrap();
+        // Disable crash supervision before touching the child so the
+        // supervisor doesn't race us and respawn a process we're killing.
+        state.should_supervise.store(false, Ordering::SeqCst);
+        state.shutdown_started.swap(true, Ordering::SeqCst)
+    };
+    if already_shutting_down {
+        return;
+    }
+
+    info!("Shutting down PocketBase sidecar...");
+    stop_child(app).await;
+    cleanup_lock(app);
+    super::commands::emit_status(app);
+}
+
+fn cleanup_lock(app: &AppHandle) {
+    if let Ok(app_data_dir) = app.path().app_data_dir() {
+        lock::remove_lock(&lock::lock_file_path(&app_data_dir));
+    }
+}
+
+/// Registers handlers for SIGINT/SIGTERM (in addition to the window-destroy
+/// event already wired up elsewhere) that run the graceful shutdown path
+/// and then exit the process.
+pub fn register_signal_handlers(app: AppHandle) {
+    let ctrl_c_app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            info!("Received Ctrl-C - starting graceful shutdown");
+            graceful_shutdown(&ctrl_c_app).await;
+            ctrl_c_app.exit(0);
+        }
+    });
+
+    #[cfg(unix)]
+    {
+        let term_app = app;
+        tauri::async_runtime::spawn(async move {
+            use tokio::signal::unix::{signal, SignalKind};
+            match signal(SignalKind::terminate()) {
+                Ok(mut stream) => {
+                    stream.recv().await;
+                    info!("Received SIGTERM - starting graceful shutdown");
+                    graceful_shutdown(&term_app).await;
+                    term_app.exit(0);
+                }
+                Err(e) => error!("Failed to register SIGTERM handler: {}", e),
+            }
+        });
+    }
+}
@@ -0,0 +1,124 @@
+//! Spawns the PocketBase sidecar and wires up its stdout/stderr logging and
+//! the crash-supervision hook that watches for unexpected termination.
+
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use log::{debug, error, info, warn};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_shell::ShellExt;
+
+use super::health;
+use super::lock;
+use super::state::PocketBaseState;
+use super::supervisor;
+
+/// Spawns PocketBase on `port` against `data_dir`, stores the child/port in
+/// state, writes the lock file, and starts the output-logging task.
+pub fn spawn_sidecar(
+    app: &AppHandle,
+    data_dir: &str,
+    port: u16,
+) -> tauri_plugin_shell::Result<()> {
+    info!("Starting PocketBase sidecar on 127.0.0.1:{}...", port);
+    let sidecar_command = app
+        .shell()
+        .sidecar("pocketbase")?
+        .args([
+            "serve",
+            "--http",
+            &format!("127.0.0.1:{}", port),
+            "--dir",
+            data_dir,
+        ]);
+
+    let (mut rx, child) = sidecar_command.spawn()?;
+    info!("PocketBase sidecar spawned successfully (pid {})", child.pid());
+
+    if let Ok(app_data_dir) = app.path().app_data_dir() {
+        let lock_path = lock::lock_file_path(&app_data_dir);
+        if let Err(e) = lock::write_lock(&lock_path, child.pid(), port) {
+            warn!("Failed to write PocketBase lock file: {}", e);
+        }
+    }
+
+    let (process_exited, generation) = {
+        let state = app.state::<Mutex<PocketBaseState>>();
+        let mut state = state.lock().unwrap();
+        state.generation += 1;
+        state.child = Some(child);
+        state.port = Some(port);
+        state.started_at = Some(Instant::now());
+        state.ready.store(false, Ordering::SeqCst);
+        state.last_exit_code = None;
+        state.external_pid = None;
+        (state.process_exited.clone(), state.generation)
+    };
+    debug!("PocketBase child process stored in state (generation {})", generation);
+
+    let app_handle = app.clone();
+    let data_dir = data_dir.to_string();
+    tauri::async_runtime::spawn(async move {
+        use tauri_plugin_shell::process::CommandEvent;
+        while let Some(event) = rx.recv().await {
+            match event {
+                CommandEvent::Stdout(line) => {
+                    let output = String::from_utf8_lossy(&line);
+                    info!("[PocketBase] {}", output.trim());
+                }
+                CommandEvent::Stderr(line) => {
+                    let output = String::from_utf8_lossy(&line);
+                    if output.contains("Error") || output.contains("error") {
+                        error!("[PocketBase] {}", output.trim());
+                    } else {
+                        warn!("[PocketBase] {}", output.trim());
+                    }
+                }
+                CommandEvent::Terminated(status) => {
+                    match status.code {
+                        Some(0) => info!("[PocketBase] Process terminated cleanly (exit code: 0)"),
+                        Some(c) => {
+                            error!("[PocketBase] Process terminated with error (exit code: {})", c)
+                        }
+                        None => warn!("[PocketBase] Process terminated (no exit code)"),
+                    }
+                    process_exited.notify_waiters();
+                    {
+                        let state = app_handle.state::<Mutex<PocketBaseState>>();
+                        let mut state = state.lock().unwrap();
+                        // Only touch state if nobody has already spawned a
+                        // replacement for this generation out from under us
+                        // (e.g. a concurrent `pocketbase_restart`).
+                        if state.generation == generation {
+                            state.child = None;
+                            state.ready.store(false, Ordering::SeqCst);
+                            // Only record this as a crash if nothing already
+                            // disabled supervision (i.e. this wasn't a
+                            // deliberate stop via shutdown or restart).
+                            if status.code != Some(0) && state.should_supervise.load(Ordering::SeqCst) {
+                                state.last_exit_code = status.code;
+                            }
+                        }
+                    }
+                    super::commands::emit_status(&app_handle);
+                    supervisor::on_terminated(&app_handle, data_dir, port, status.code, generation).await;
+                    break;
+                }
+                CommandEvent::Error(err) => {
+                    error!("[PocketBase] Process error: {}", err);
+                }
+                _ => {}
+            }
+        }
+    });
+
+    let ready_app_handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        health::wait_until_ready(&ready_app_handle, port, generation).await;
+    });
+
+    super::commands::emit_status(app);
+
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::Instant;
+
+use tauri_plugin_shell::process::CommandChild;
+use tokio::sync::Notify;
+
+/// Shared state for the managed PocketBase sidecar.
+///
+/// Lives behind a `Mutex` in Tauri's state container; commands and the
+/// setup/shutdown hooks all reach it through `app.state::<Mutex<PocketBaseState>>()`.
+pub struct PocketBaseState {
+    pub child: Option<CommandChild>,
+    /// Port the sidecar is (or was) negotiated to run on.
+    pub port: Option<u16>,
+    /// Notified by the stdout-logging task when it observes `CommandEvent::Terminated`,
+    /// so the graceful-shutdown path can wait for the sidecar to actually exit.
+    pub process_exited: Arc<Notify>,
+    /// Set once graceful shutdown has started, so it only runs once even if
+    /// both an OS signal and the window-destroyed event fire.
+    pub shutdown_started: Arc<AtomicBool>,
+    /// Whether the crash supervisor should respawn the sidecar on an
+    /// unexpected exit. Disabled by the graceful-shutdown path before it
+    /// kills the child, so a deliberate stop isn't mistaken for a crash.
+    pub should_supervise: Arc<AtomicBool>,
+    /// Consecutive crash-restart attempts since the sidecar last stayed up
+    /// through its stability window. Drives the exponential backoff delay.
+    pub restart_attempts: u32,
+    /// When the current child was spawned, for `pocketbase_status`'s uptime.
+    pub started_at: Option<Instant>,
+    /// Set once the health probe has observed a 200 from `/api/health` for
+    /// the current child. Cleared on every (re)spawn.
+    pub ready: Arc<AtomicBool>,
+    /// Exit code of the most recent unexpected termination, for reporting a
+    /// `Crashed` status after the supervisor gives up.
+    pub last_exit_code: Option<i32>,
+    /// PID of a foreign PocketBase instance we're reusing via the lock file
+    /// (we don't hold a `CommandChild` for it, so `child` stays `None`).
+    pub external_pid: Option<u32>,
+    /// Bumped every time `spawn_sidecar` stores a new child. Lets a task
+    /// that slept across an await point (e.g. the supervisor's backoff
+    /// delay) tell whether it's still looking at the process it started
+    /// with, or whether someone else already spawned a replacement.
+    pub generation: u64,
+}
+
+impl PocketBaseState {
+    pub fn new() -> Self {
+        Self {
+            child: None,
+            port: None,
+            process_exited: Arc::new(Notify::new()),
+            shutdown_started: Arc::new(AtomicBool::new(false)),
+            should_supervise: Arc::new(AtomicBool::new(true)),
+            restart_attempts: 0,
+            started_at: None,
+            ready: Arc::new(AtomicBool::new(false)),
+            last_exit_code: None,
+            external_pid: None,
+            generation: 0,
+        }
+    }
+}
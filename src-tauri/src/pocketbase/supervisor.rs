@@ -0,0 +1,115 @@
+//! Crash supervision: respawns the PocketBase sidecar after an unexpected
+//! termination, with capped exponential backoff.
+
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use log::{error, info, warn};
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::commands;
+use super::spawn::spawn_sidecar;
+use super::state::PocketBaseState;
+
+const BASE_DELAY: Duration = Duration::from_millis(500);
+const MAX_DELAY: Duration = Duration::from_secs(30);
+const STABILITY_WINDOW: Duration = Duration::from_secs(10);
+const MAX_CONSECUTIVE_FAILURES: u32 = 6;
+
+/// Emitted to the frontend when the supervisor gives up restarting.
+pub const FATAL_EVENT: &str = "pocketbase://fatal";
+
+/// True if shutdown has started, supervision has been disabled, or a newer
+/// generation has been spawned since `generation` was captured - in any of
+/// those cases a scheduled respawn should be abandoned rather than acted on.
+fn respawn_superseded(state: &PocketBaseState, generation: u64) -> bool {
+    state.shutdown_started.load(Ordering::SeqCst)
+        || !state.should_supervise.load(Ordering::SeqCst)
+        || state.generation != generation
+}
+
+/// Called by the sidecar's logging task when it observes
+/// `CommandEvent::Terminated`. Restarts the sidecar on the same port with
+/// exponential backoff, unless shutdown is in progress, supervision has
+/// been disabled, or the exit was a clean one (exit code 0).
+///
+/// `generation` is the generation of the process that just terminated, as
+/// recorded in `PocketBaseState` when it was spawned.
+pub async fn on_terminated(
+    app: &AppHandle,
+    data_dir: String,
+    port: u16,
+    code: Option<i32>,
+    generation: u64,
+) {
+    if code == Some(0) {
+        return;
+    }
+
+    let state = app.state::<Mutex<PocketBaseState>>();
+
+    {
+        let state = state.lock().unwrap();
+        if respawn_superseded(&state, generation) {
+            return;
+        }
+    }
+
+    let attempt = {
+        let mut state = state.lock().unwrap();
+        state.restart_attempts += 1;
+        state.restart_attempts
+    };
+
+    if attempt > MAX_CONSECUTIVE_FAILURES {
+        error!(
+            "PocketBase crashed {} times in a row - giving up on restarting it",
+            attempt - 1
+        );
+        let _ = app.emit(FATAL_EVENT, "PocketBase repeatedly failed to stay running");
+        commands::emit_status(app);
+        return;
+    }
+
+    let delay = std::cmp::min(BASE_DELAY * 2u32.pow(attempt.saturating_sub(1)), MAX_DELAY);
+    warn!(
+        "PocketBase exited unexpectedly (restart attempt {}) - retrying in {:?}",
+        attempt, delay
+    );
+    tokio::time::sleep(delay).await;
+
+    // Re-check after waking: shutdown may have started, supervision may
+    // have been disabled, or someone else (e.g. a manual `pocketbase_restart`)
+    // may already have spawned a replacement while we were sleeping. Any of
+    // those means spawning here would clobber the current, already-managed
+    // process and leak it as an untracked orphan.
+    {
+        let state = state.lock().unwrap();
+        if respawn_superseded(&state, generation) {
+            info!("Skipping scheduled PocketBase respawn - state changed while backing off");
+            return;
+        }
+    }
+
+    let new_generation = if let Err(e) = spawn_sidecar(app, &data_dir, port) {
+        error!("Failed to respawn PocketBase sidecar: {}", e);
+        return;
+    } else {
+        state.lock().unwrap().generation
+    };
+
+    // Reset the failure counter once the respawned process survives a
+    // stability window, so a later isolated crash starts backoff from zero.
+    // Only do so if it's still the process we just spawned - a faster crash
+    // loop may already be several generations ahead by the time this fires.
+    let app_handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(STABILITY_WINDOW).await;
+        let state = app_handle.state::<Mutex<PocketBaseState>>();
+        let mut state = state.lock().unwrap();
+        if state.generation == new_generation && state.child.is_some() {
+            state.restart_attempts = 0;
+        }
+    });
+}